@@ -1,9 +1,46 @@
-use rodio::source::Source;
-use rodio::{Decoder, OutputStreamBuilder};
-use std::io::Cursor;
-use std::thread;
+use cpal::traits::{DeviceTrait, HostTrait};
+use rodio::source::{Buffered, Source};
+use rodio::{Decoder, OutputStream, OutputStreamBuilder, Sink};
+use std::fs::File;
+use std::io::{BufReader, Cursor};
+use std::sync::Mutex;
 use std::time::Duration;
 
+use crate::settings::{SoundConfig, SoundCueConfig};
+
+/// Name + id of an available audio output device, as returned to the
+/// frontend for device selection. IDs are the cpal device name, mirroring
+/// how `selected_mic_id` scopes input.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OutputDeviceInfo {
+    pub id: String,
+    pub name: String,
+}
+
+/// Enumerate the host's available output devices
+pub fn list_output_devices() -> Vec<OutputDeviceInfo> {
+    let host = cpal::default_host();
+    let Ok(devices) = host.output_devices() else {
+        return Vec::new();
+    };
+
+    devices
+        .filter_map(|device| {
+            device.name().ok().map(|name| OutputDeviceInfo {
+                id: name.clone(),
+                name,
+            })
+        })
+        .collect()
+}
+
+fn find_output_device(id: &str) -> Option<cpal::Device> {
+    let host = cpal::default_host();
+    host.output_devices()
+        .ok()?
+        .find(|device| device.name().map(|name| name == id).unwrap_or(false))
+}
+
 /// Types of sounds that can be played
 #[derive(Debug, Clone, Copy)]
 pub enum SoundType {
@@ -13,36 +50,206 @@ pub enum SoundType {
 
 const START_SOUND: &[u8] = include_bytes!("assets/start.mp3");
 const STOP_SOUND: &[u8] = include_bytes!("assets/stop.mp3");
-const DEFAULT_AUDIO_PLAYBACK_DURATION_MS: u64 = 500;
+const DEFAULT_VOLUME: f32 = 0.3;
+
+/// Cues are decoded (and, for custom sound packs, trimmed/gained) once into
+/// a `Buffered` source, so subsequent plays are cheap clones instead of a
+/// fresh decode
+type CueSource = Buffered<Box<dyn Source<Item = f32> + Send>>;
+
+struct AudioInner {
+    stream: OutputStream,
+    sink: Sink,
+    start_cue: CueSource,
+    stop_cue: CueSource,
+    config: SoundConfig,
+    output_device_id: Option<String>,
+}
+
+/// Long-lived audio engine held in Tauri state: the output device is opened
+/// once at startup and kept alive via a `Sink`, and the start/stop cues are
+/// decoded a single time. Playing a cue is then just `sink.append` with no
+/// new thread, device re-open, or re-decode.
+pub struct AudioEngine {
+    inner: Mutex<AudioInner>,
+}
+
+impl AudioEngine {
+    /// Open the configured (or default) output device and decode the
+    /// configured cues, falling back to the embedded defaults for any cue
+    /// without a custom path, or whose custom path fails to load
+    pub fn new(config: SoundConfig, output_device_id: Option<String>) -> Result<Self, String> {
+        Ok(Self {
+            inner: Mutex::new(Self::open(config, output_device_id)?),
+        })
+    }
+
+    fn open(config: SoundConfig, output_device_id: Option<String>) -> Result<AudioInner, String> {
+        let stream = open_stream(output_device_id.as_deref())?;
+        let sink = Sink::connect_new(stream.mixer());
+        sink.set_volume(DEFAULT_VOLUME);
+
+        let start_cue = resolve_cue(&config.recording_start, START_SOUND)?;
+        let stop_cue = resolve_cue(&config.recording_stop, STOP_SOUND)?;
+
+        Ok(AudioInner {
+            stream,
+            sink,
+            start_cue,
+            stop_cue,
+            config,
+            output_device_id,
+        })
+    }
 
-/// Play a sound effect (non-blocking)
-pub fn play_sound(sound_type: SoundType) {
-    thread::spawn(move || {
-        if let Err(e) = play_sound_blocking(sound_type) {
-            log::warn!("Failed to play sound: {}", e);
+    /// Play a cue (non-blocking): clones the pre-decoded source onto the
+    /// already-open sink
+    pub fn play(&self, sound_type: SoundType) {
+        let inner = match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        let cue = match sound_type {
+            SoundType::RecordingStart => inner.start_cue.clone(),
+            SoundType::RecordingStop => inner.stop_cue.clone(),
+        };
+
+        inner.sink.append(cue);
+    }
+
+    /// Adjust the master cue playback volume at runtime (replaces the old
+    /// hardcoded `amplify(0.3)`); independent of each cue's own volume
+    pub fn set_volume(&self, volume: f32) {
+        let inner = match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        inner.sink.set_volume(volume);
+    }
+
+    /// Re-decode the start/stop cues from an updated `SoundConfig`, e.g.
+    /// after the user picks a new sound pack in settings. Each cue is only
+    /// re-decoded if it actually changed, so saving settings with one cue
+    /// edited doesn't also re-read and re-decode the other cue's file.
+    pub fn update_sound_config(&self, config: SoundConfig) -> Result<(), String> {
+        let mut inner = match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        if config.recording_start != inner.config.recording_start {
+            inner.start_cue = resolve_cue(&config.recording_start, START_SOUND)?;
         }
-    });
+        if config.recording_stop != inner.config.recording_stop {
+            inner.stop_cue = resolve_cue(&config.recording_stop, STOP_SOUND)?;
+        }
+        inner.config = config;
+        Ok(())
+    }
+
+    /// Reopen the output stream and sink against the configured device (or
+    /// the default, if none is configured or it can no longer be found),
+    /// keeping the already-decoded cues. Call this when the OS reports the
+    /// default output device changed.
+    pub fn reopen(&self) -> Result<(), String> {
+        let mut inner = match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        let volume = inner.sink.volume();
+        let stream = open_stream(inner.output_device_id.as_deref())?;
+        let sink = Sink::connect_new(stream.mixer());
+        sink.set_volume(volume);
+
+        inner.stream = stream;
+        inner.sink = sink;
+        Ok(())
+    }
+
+    /// Switch to a new output device (or back to the default, if `None`),
+    /// persisting the choice on the engine so a later `reopen()` (e.g. after
+    /// a device hot-plug event) keeps using it
+    pub fn set_output_device(&self, device_id: Option<String>) -> Result<(), String> {
+        let mut inner = match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        let volume = inner.sink.volume();
+        let stream = open_stream(device_id.as_deref())?;
+        let sink = Sink::connect_new(stream.mixer());
+        sink.set_volume(volume);
+
+        inner.stream = stream;
+        inner.sink = sink;
+        inner.output_device_id = device_id;
+        Ok(())
+    }
 }
 
-fn play_sound_blocking(
-    sound_type: SoundType,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let stream = OutputStreamBuilder::open_default_stream()?;
+/// Open an output stream on the device named by `device_id`, falling back to
+/// the system default when `device_id` is `None` or no longer present
+fn open_stream(device_id: Option<&str>) -> Result<OutputStream, String> {
+    let device = match device_id {
+        Some(id) => match find_output_device(id) {
+            Some(device) => Some(device),
+            None => {
+                log::warn!("Configured output device '{}' not found, falling back to default", id);
+                None
+            }
+        },
+        None => None,
+    };
 
-    let sound_data = match sound_type {
-        SoundType::RecordingStart => START_SOUND,
-        SoundType::RecordingStop => STOP_SOUND,
+    match device {
+        Some(device) => OutputStreamBuilder::from_device(device)
+            .and_then(|builder| builder.open_stream())
+            .map_err(|e| format!("Failed to open audio output stream: {}", e)),
+        None => OutputStreamBuilder::open_default_stream()
+            .map_err(|e| format!("Failed to open audio output stream: {}", e)),
+    }
+}
+
+/// Resolve a cue's source: the custom path if set and decodable (trimmed to
+/// `start_ms..end_ms` and gained by `volume`), otherwise the embedded
+/// default at the cue's configured volume
+fn resolve_cue(cue: &SoundCueConfig, embedded: &'static [u8]) -> Result<CueSource, String> {
+    let raw: Box<dyn Source<Item = f32> + Send> = match decode_custom_source(cue) {
+        Some(source) => source,
+        None => {
+            if cue.path.is_some() {
+                log::warn!("Custom sound path missing or undecodable, falling back to default cue");
+            }
+            let decoder = Decoder::new(Cursor::new(embedded))
+                .map_err(|e| format!("Failed to decode default sound: {}", e))?;
+            Box::new(decoder)
+        }
     };
 
-    let cursor = Cursor::new(sound_data);
-    let source = Decoder::new(cursor)?.amplify(0.3);
+    let gained: Box<dyn Source<Item = f32> + Send> = Box::new(raw.amplify(cue.volume));
+    Ok(gained.buffered())
+}
 
-    let duration = source
-        .total_duration()
-        .unwrap_or(Duration::from_millis(DEFAULT_AUDIO_PLAYBACK_DURATION_MS));
+/// Decode and trim a custom sound file. Returns `None` (rather than an
+/// error) if the path is unset, missing, or fails to decode, so the caller
+/// can fall back to the embedded default.
+fn decode_custom_source(cue: &SoundCueConfig) -> Option<Box<dyn Source<Item = f32> + Send>> {
+    let path = cue.path.as_ref()?;
+    let file = File::open(path).ok()?;
+    let decoder = Decoder::new(BufReader::new(file)).ok()?;
 
-    stream.mixer().add(source);
-    thread::sleep(duration + Duration::from_millis(50));
+    let source: Box<dyn Source<Item = f32> + Send> = match (cue.start_ms, cue.end_ms) {
+        (Some(start), Some(end)) if end > start => Box::new(
+            decoder
+                .skip_duration(Duration::from_millis(start))
+                .take_duration(Duration::from_millis(end - start)),
+        ),
+        (Some(start), None) => Box::new(decoder.skip_duration(Duration::from_millis(start))),
+        (None, Some(end)) => Box::new(decoder.take_duration(Duration::from_millis(end))),
+        _ => Box::new(decoder),
+    };
 
-    Ok(())
+    Some(source)
 }
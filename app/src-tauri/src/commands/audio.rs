@@ -0,0 +1,40 @@
+use crate::audio::{self, AudioEngine, OutputDeviceInfo};
+use crate::settings::{validate_sound_cue_config, SoundConfig};
+use tauri::State;
+
+/// Update the recording cue sound pack, validating each cue's path/trim
+/// range before re-decoding
+#[tauri::command]
+pub async fn set_sound_config(
+    config: SoundConfig,
+    audio: State<'_, AudioEngine>,
+) -> Result<(), String> {
+    validate_sound_cue_config(&config.recording_start).map_err(|e| e.to_string())?;
+    validate_sound_cue_config(&config.recording_stop).map_err(|e| e.to_string())?;
+
+    audio.update_sound_config(config)
+}
+
+/// Adjust the master playback volume for recording cues
+#[tauri::command]
+pub async fn set_sound_volume(volume: f32, audio: State<'_, AudioEngine>) -> Result<(), String> {
+    audio.set_volume(volume);
+    Ok(())
+}
+
+/// List the host's available audio output devices, for a device picker in
+/// settings
+#[tauri::command]
+pub async fn list_output_devices() -> Result<Vec<OutputDeviceInfo>, String> {
+    Ok(audio::list_output_devices())
+}
+
+/// Switch playback cues to a different output device (or back to the
+/// default, if `device_id` is `None`)
+#[tauri::command]
+pub async fn set_output_device(
+    device_id: Option<String>,
+    audio: State<'_, AudioEngine>,
+) -> Result<(), String> {
+    audio.set_output_device(device_id)
+}
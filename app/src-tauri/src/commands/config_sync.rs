@@ -1,20 +1,45 @@
 use crate::config_sync::ConfigSync;
 use crate::events::{ConfigResponse, ConfigSetting, EventName};
+use crate::history::HistoryStorage;
+use crate::history_sync::{HistorySync, HistorySyncResponse, HISTORY_SYNC_EVENT};
 use tauri::{AppHandle, Emitter};
 
 /// Notify Rust that we've connected to the server
-/// This stores connection info and syncs current settings
+/// This stores connection info and syncs current settings. If
+/// `history_passphrase` is provided, it also connects history sync and runs
+/// an initial download + upload pass.
 #[tauri::command]
 pub async fn set_server_connected(
     app: AppHandle,
     server_url: String,
     client_uuid: String,
+    history_passphrase: Option<String>,
     config_sync: tauri::State<'_, ConfigSync>,
+    history_sync: tauri::State<'_, HistorySync>,
+    history: tauri::State<'_, HistoryStorage>,
 ) -> Result<(), String> {
     // Store connection info
     {
         let mut sync = config_sync.write().await;
-        sync.set_connected(server_url, client_uuid);
+        sync.set_connected(server_url.clone(), client_uuid.clone()).await;
+    }
+
+    if let Some(passphrase) = history_passphrase {
+        let connect_result = {
+            let mut sync = history_sync.write().await;
+            sync.set_connected(server_url, client_uuid, &passphrase, &history)
+                .await
+        };
+
+        if let Err(e) = connect_result {
+            log::warn!("Failed to set up history sync: {}", e);
+            let _ = app.emit(
+                HISTORY_SYNC_EVENT,
+                HistorySyncResponse::Error { message: e },
+            );
+        } else {
+            sync_history(&app, &history_sync, &history).await;
+        }
     }
 
     // Sync current settings to server
@@ -61,12 +86,61 @@ pub async fn set_server_connected(
 }
 
 /// Notify Rust that we've disconnected from the server
-/// This disables config syncing
+/// This disables config and history syncing
 #[tauri::command]
 pub async fn set_server_disconnected(
     config_sync: tauri::State<'_, ConfigSync>,
+    history_sync: tauri::State<'_, HistorySync>,
 ) -> Result<(), String> {
-    let mut sync = config_sync.write().await;
-    sync.set_disconnected();
+    {
+        let mut sync = config_sync.write().await;
+        sync.set_disconnected();
+    }
+    {
+        let mut sync = history_sync.write().await;
+        sync.set_disconnected();
+    }
     Ok(())
 }
+
+/// Download remote entries then upload any new local ones, emitting
+/// `HISTORY_SYNC_EVENT` for each step so the UI can surface sync state
+async fn sync_history(
+    app: &AppHandle,
+    history_sync: &tauri::State<'_, HistorySync>,
+    history: &tauri::State<'_, HistoryStorage>,
+) {
+    let download_result = {
+        let sync = history_sync.read().await;
+        sync.download_entries(history).await
+    };
+    match download_result {
+        Ok(count) => {
+            let _ = app.emit(HISTORY_SYNC_EVENT, HistorySyncResponse::Downloaded { count });
+        }
+        Err(e) => {
+            log::warn!("Failed to download history on connect: {}", e);
+            let _ = app.emit(
+                HISTORY_SYNC_EVENT,
+                HistorySyncResponse::Error { message: e },
+            );
+        }
+    }
+
+    let upload_result = {
+        let mut sync = history_sync.write().await;
+        sync.upload_new_entries(history).await
+    };
+    match upload_result {
+        Ok(count) => {
+            let _ = app.emit(HISTORY_SYNC_EVENT, HistorySyncResponse::Uploaded { count });
+        }
+        Err(e) => {
+            log::warn!("Failed to upload history on connect: {}", e);
+            let _ = app.emit(
+                HISTORY_SYNC_EVENT,
+                HistorySyncResponse::Error { message: e },
+            );
+        }
+    }
+}
@@ -1,14 +1,42 @@
-use crate::history::{HistoryEntry, HistoryStorage};
-use tauri::State;
+use crate::history::{AddEntryOutcome, AddOptions, HistoryEntry, HistoryStorage};
+use crate::search::{SearchMatch, SearchOpts};
+use tauri::{AppHandle, State};
 
-/// Add a new entry to the dictation history
+/// Add a new entry to the dictation history. The result reports whether the
+/// entry was stored, redacted, or suppressed (whitespace-only, a consecutive
+/// duplicate, or a skipped secret match).
 #[tauri::command]
 pub async fn add_history_entry(
+    app: AppHandle,
     text: String,
     raw_text: String,
     history: State<'_, HistoryStorage>,
-) -> Result<HistoryEntry, String> {
-    history.add_entry(text, raw_text)
+) -> Result<AddEntryOutcome, String> {
+    let settings = super::settings::get_settings(app)?;
+    let opts = AddOptions {
+        ignore_dups: settings.history_ignore_dups,
+        ignore_space: settings.history_ignore_space,
+    };
+    history.add_entry(text, raw_text, opts).await
+}
+
+/// Search dictation history by substring, prefix, or fuzzy match
+#[tauri::command]
+pub async fn search_history(
+    query: String,
+    opts: SearchOpts,
+    history: State<'_, HistoryStorage>,
+) -> Result<Vec<SearchMatch>, String> {
+    history.search(&query, opts).await
+}
+
+/// Reload the secret filter's user-supplied patterns without restarting
+#[tauri::command]
+pub async fn reload_secret_filter_patterns(
+    patterns: Vec<String>,
+    history: State<'_, HistoryStorage>,
+) -> Result<(), String> {
+    history.reload_secret_filter_patterns(patterns)
 }
 
 /// Get dictation history entries
@@ -17,7 +45,7 @@ pub async fn get_history(
     limit: Option<usize>,
     history: State<'_, HistoryStorage>,
 ) -> Result<Vec<HistoryEntry>, String> {
-    history.get_all(limit)
+    history.get_all(limit).await
 }
 
 /// Delete a history entry by ID
@@ -26,11 +54,11 @@ pub async fn delete_history_entry(
     id: String,
     history: State<'_, HistoryStorage>,
 ) -> Result<bool, String> {
-    history.delete(&id)
+    history.delete(&id).await
 }
 
 /// Clear all history entries
 #[tauri::command]
 pub async fn clear_history(history: State<'_, HistoryStorage>) -> Result<(), String> {
-    history.clear()
+    history.clear().await
 }
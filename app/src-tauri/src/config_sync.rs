@@ -1,5 +1,6 @@
 use serde::Serialize;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::time::Duration;
 use tauri_plugin_http::reqwest::Client;
 use tokio::sync::RwLock;
@@ -9,11 +10,24 @@ use crate::settings::CleanupPromptSections;
 /// Default STT timeout in seconds (matches server's DEFAULT_TRANSCRIPTION_WAIT_TIMEOUT_SECONDS)
 pub const DEFAULT_STT_TIMEOUT_SECONDS: f64 = 0.5;
 
+/// Number of attempts for a single PUT before giving up and re-queuing
+const MAX_SYNC_ATTEMPTS: u32 = 3;
+
+/// Latest not-yet-delivered value per endpoint. A newer write to the same
+/// slot overwrites the older one, so we only ever replay the most recent
+/// state instead of a stale history of edits.
+#[derive(Default)]
+struct PendingSync {
+    prompt_sections: Option<CleanupPromptSections>,
+    stt_timeout: Option<f64>,
+}
+
 /// Tracks server connection state for config syncing
 pub struct ConfigSyncState {
     client: Client,
     server_url: Option<String>,
     client_uuid: Option<String>,
+    pending: Mutex<PendingSync>,
 }
 
 impl Default for ConfigSyncState {
@@ -31,11 +45,13 @@ impl ConfigSyncState {
                 .expect("Failed to create HTTP client"),
             server_url: None,
             client_uuid: None,
+            pending: Mutex::new(PendingSync::default()),
         }
     }
 
-    /// Set connection info when connected to server
-    pub fn set_connected(&mut self, server_url: String, client_uuid: String) {
+    /// Set connection info when connected to server, then flush any edits
+    /// that were queued while disconnected
+    pub async fn set_connected(&mut self, server_url: String, client_uuid: String) {
         log::info!(
             "Config sync connected: {} (uuid: {})",
             server_url,
@@ -43,6 +59,8 @@ impl ConfigSyncState {
         );
         self.server_url = Some(server_url);
         self.client_uuid = Some(client_uuid);
+
+        self.flush_pending().await;
     }
 
     /// Clear connection info when disconnected
@@ -57,35 +75,69 @@ impl ConfigSyncState {
         self.server_url.is_some() && self.client_uuid.is_some()
     }
 
-    /// Sync prompt sections to server (best-effort, logs errors)
+    /// Send any values queued while disconnected or mid-retry
+    async fn flush_pending(&self) {
+        let (prompt_sections, stt_timeout) = {
+            let mut pending = self.pending.lock().unwrap();
+            (pending.prompt_sections.take(), pending.stt_timeout.take())
+        };
+
+        if let Some(sections) = prompt_sections {
+            if let Err(e) = self.sync_prompt_sections(&sections).await {
+                log::warn!("Failed to flush queued prompt sections: {}", e);
+            }
+        }
+
+        if let Some(timeout) = stt_timeout {
+            if let Err(e) = self.sync_stt_timeout(timeout).await {
+                log::warn!("Failed to flush queued STT timeout: {}", e);
+            }
+        }
+    }
+
+    /// Sync prompt sections to server. Retries transport errors with
+    /// exponential backoff; if not connected, or if every retry fails,
+    /// queues the value so it is sent on the next `set_connected`.
     pub async fn sync_prompt_sections(
         &self,
         sections: &CleanupPromptSections,
     ) -> Result<(), String> {
         let (url, uuid) = match (&self.server_url, &self.client_uuid) {
             (Some(u), Some(id)) => (u, id),
-            _ => return Ok(()), // Not connected, skip silently
+            _ => {
+                self.pending.lock().unwrap().prompt_sections = Some(sections.clone());
+                return Ok(());
+            }
         };
 
-        self.client
-            .put(format!("{}/api/config/prompts", url))
-            .header("X-Client-UUID", uuid)
-            .json(sections)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?
-            .error_for_status()
-            .map_err(|e| e.to_string())?;
+        let result = send_with_retry(|| {
+            self.client
+                .put(format!("{}/api/config/prompts", url))
+                .header("X-Client-UUID", uuid)
+                .json(sections)
+                .send()
+        })
+        .await;
+
+        if let Err(e) = &result {
+            self.pending.lock().unwrap().prompt_sections = Some(sections.clone());
+            return Err(e.clone());
+        }
 
         log::debug!("Synced prompt sections to server");
         Ok(())
     }
 
-    /// Sync STT timeout to server
+    /// Sync STT timeout to server. Retries transport errors with
+    /// exponential backoff; if not connected, or if every retry fails,
+    /// queues the value so it is sent on the next `set_connected`.
     pub async fn sync_stt_timeout(&self, timeout_seconds: f64) -> Result<(), String> {
         let (url, uuid) = match (&self.server_url, &self.client_uuid) {
             (Some(u), Some(id)) => (u, id),
-            _ => return Ok(()), // Not connected, skip silently
+            _ => {
+                self.pending.lock().unwrap().stt_timeout = Some(timeout_seconds);
+                return Ok(());
+            }
         };
 
         #[derive(Serialize)]
@@ -93,21 +145,57 @@ impl ConfigSyncState {
             timeout_seconds: f64,
         }
 
-        self.client
-            .put(format!("{}/api/config/stt-timeout", url))
-            .header("X-Client-UUID", uuid)
-            .json(&TimeoutBody { timeout_seconds })
-            .send()
-            .await
-            .map_err(|e| e.to_string())?
-            .error_for_status()
-            .map_err(|e| e.to_string())?;
+        let result = send_with_retry(|| {
+            self.client
+                .put(format!("{}/api/config/stt-timeout", url))
+                .header("X-Client-UUID", uuid)
+                .json(&TimeoutBody { timeout_seconds })
+                .send()
+        })
+        .await;
+
+        if let Err(e) = &result {
+            self.pending.lock().unwrap().stt_timeout = Some(timeout_seconds);
+            return Err(e.clone());
+        }
 
         log::debug!("Synced STT timeout ({}) to server", timeout_seconds);
         Ok(())
     }
 }
 
+/// Send a PUT built by `request`, making up to `MAX_SYNC_ATTEMPTS` attempts
+/// in total with exponential backoff between them (1s, then 2s, ...) on
+/// transport errors. A 4xx/5xx
+/// response (`error_for_status`) is not retried, since resending the same
+/// request won't change the server's answer.
+async fn send_with_retry<F>(request: impl Fn() -> F) -> Result<(), String>
+where
+    F: std::future::Future<
+        Output = Result<tauri_plugin_http::reqwest::Response, tauri_plugin_http::reqwest::Error>,
+    >,
+{
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        match request().await {
+            Ok(response) => return response.error_for_status().map(|_| ()).map_err(|e| e.to_string()),
+            Err(e) if attempt < MAX_SYNC_ATTEMPTS => {
+                let backoff = Duration::from_secs(1 << (attempt - 1));
+                log::warn!(
+                    "Config sync request failed ({}), retrying in {:?} (attempt {}/{})",
+                    e,
+                    backoff,
+                    attempt,
+                    MAX_SYNC_ATTEMPTS
+                );
+                tokio::time::sleep(backoff).await;
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
+
 pub type ConfigSync = Arc<RwLock<ConfigSyncState>>;
 
 pub fn new_config_sync() -> ConfigSync {
@@ -1,12 +1,20 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::RwLock;
 use uuid::Uuid;
 
-const MAX_HISTORY_ENTRIES: usize = 500;
+use crate::search::{self, SearchMatch, SearchOpts};
+use crate::secret_filter::{SecretFilter, SecretFilterMode};
+
+/// Default retention cap on stored entries, applied as a delete-oldest sweep
+/// after each insert. `None` disables retention entirely. This is only a
+/// suggested default for whoever sources `retention_limit` from settings;
+/// `HistoryStorage::new` stores whatever it's given as-is, so passing `None`
+/// genuinely disables retention instead of falling back to this value.
+pub const DEFAULT_RETENTION_LIMIT: Option<usize> = Some(500);
 
 /// Strategy for importing history entries
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -28,6 +36,48 @@ pub struct HistoryImportResult {
     pub entries_skipped: Option<usize>,
 }
 
+/// Per-call overrides for `HistoryStorage::add_entry`, normally sourced from
+/// `AppSettings`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AddOptions {
+    /// Skip insertion if the text equals the current newest entry
+    pub ignore_dups: bool,
+    /// Skip insertion if the text is empty or all-whitespace
+    pub ignore_space: bool,
+}
+
+impl Default for AddOptions {
+    fn default() -> Self {
+        Self {
+            ignore_dups: true,
+            ignore_space: true,
+        }
+    }
+}
+
+/// Why `HistoryStorage::add_entry` declined to store an entry
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SuppressReason {
+    /// Text was empty or all-whitespace
+    Whitespace,
+    /// Text matched the current newest entry
+    ConsecutiveDuplicate,
+    /// Text matched a secret-like pattern and the filter mode is `Skip`
+    Secret,
+}
+
+/// Outcome of `HistoryStorage::add_entry`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AddEntryOutcome {
+    /// The entry was stored; `redacted` reports whether the secret filter
+    /// matched and replaced part of the text
+    Stored { entry: HistoryEntry, redacted: bool },
+    /// The entry was not stored
+    Suppressed { reason: SuppressReason },
+}
+
 /// A single dictation history entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
@@ -39,9 +89,12 @@ pub struct HistoryEntry {
 }
 
 impl HistoryEntry {
+    /// IDs are UUIDv7 (Unix-millis timestamp in the high bits, random in the
+    /// low bits), so they sort the same way as `timestamp` and stay
+    /// monotonic even when entries are merged in from another device.
     pub fn new(text: String, raw_text: String) -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: Uuid::now_v7().to_string(),
             timestamp: Utc::now(),
             text,
             raw_text,
@@ -49,194 +102,393 @@ impl HistoryEntry {
     }
 }
 
-/// Storage for dictation history entries
-#[derive(Debug, Serialize, Deserialize, Default)]
-struct HistoryData {
+/// Row shape returned by the `history` table, converted into `HistoryEntry`
+#[derive(sqlx::FromRow)]
+struct HistoryRow {
+    id: String,
+    timestamp: i64,
+    text: String,
+    raw_text: String,
+}
+
+impl From<HistoryRow> for HistoryEntry {
+    fn from(row: HistoryRow) -> Self {
+        Self {
+            id: row.id,
+            timestamp: DateTime::from_timestamp_millis(row.timestamp).unwrap_or_else(Utc::now),
+            text: row.text,
+            raw_text: row.raw_text,
+        }
+    }
+}
+
+/// Legacy on-disk shape of `history.json`, kept only for the one-time import
+#[derive(Debug, Deserialize)]
+struct LegacyHistoryData {
     entries: Vec<HistoryEntry>,
 }
 
-/// Manages loading and saving of dictation history
+/// Manages the SQLite-backed dictation history store
 pub struct HistoryStorage {
-    data: RwLock<HistoryData>,
-    file_path: PathBuf,
+    pool: SqlitePool,
+    secret_filter_mode: RwLock<SecretFilterMode>,
+    secret_filter: RwLock<SecretFilter>,
+    retention_limit: RwLock<Option<usize>>,
 }
 
 impl HistoryStorage {
-    /// Create a new history storage with the given app data directory
-    pub fn new(app_data_dir: PathBuf) -> Self {
-        let file_path = app_data_dir.join("history.json");
+    /// Open (creating if necessary) the `history.db` SQLite store in the
+    /// given app data directory, apply migrations, and import any
+    /// pre-existing `history.json` on first launch
+    pub async fn new(
+        app_data_dir: PathBuf,
+        secret_filter_mode: SecretFilterMode,
+        secret_filter_patterns: Vec<String>,
+        retention_limit: Option<usize>,
+    ) -> Result<Self, String> {
+        fs::create_dir_all(&app_data_dir)
+            .map_err(|e| format!("Failed to create app data dir: {}", e))?;
+
+        let db_path = app_data_dir.join("history.db");
+        let connect_options = SqliteConnectOptions::new()
+            .filename(&db_path)
+            .create_if_missing(true);
+
+        let pool = SqlitePoolOptions::new()
+            .connect_with(connect_options)
+            .await
+            .map_err(|e| format!("Failed to open history database: {}", e))?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS history (
+                id TEXT PRIMARY KEY,
+                timestamp INTEGER NOT NULL,
+                text TEXT NOT NULL,
+                raw_text TEXT NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to create history table: {}", e))?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_history_timestamp ON history(timestamp)")
+            .execute(&pool)
+            .await
+            .map_err(|e| format!("Failed to create history index: {}", e))?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS sync_meta (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to create sync_meta table: {}", e))?;
+
+        let secret_filter = SecretFilter::new(&secret_filter_patterns).unwrap_or_else(|e| {
+            log::warn!(
+                "Invalid secret filter pattern, falling back to defaults: {}",
+                e
+            );
+            SecretFilter::default_only()
+        });
+
+        let storage = Self {
+            pool,
+            secret_filter_mode: RwLock::new(secret_filter_mode),
+            secret_filter: RwLock::new(secret_filter),
+            retention_limit: RwLock::new(retention_limit),
+        };
 
-        if let Some(parent) = file_path.parent() {
-            let _ = fs::create_dir_all(parent);
-        }
+        storage.import_legacy_json(&app_data_dir).await;
 
-        let data = Self::load_from_file(&file_path).unwrap_or_default();
+        Ok(storage)
+    }
 
-        Self {
-            data: RwLock::new(data),
-            file_path,
+    /// One-time import of a pre-SQLite `history.json`, if present. The file
+    /// is renamed afterwards so it isn't re-imported on the next launch.
+    async fn import_legacy_json(&self, app_data_dir: &Path) {
+        let json_path = app_data_dir.join("history.json");
+        let Ok(content) = fs::read_to_string(&json_path) else {
+            return;
+        };
+
+        let data = match serde_json::from_str::<LegacyHistoryData>(&content) {
+            Ok(data) => data,
+            Err(e) => {
+                log::warn!("Found history.json but failed to parse it, skipping import: {}", e);
+                return;
+            }
+        };
+
+        match self
+            .import_entries(data.entries, HistoryImportStrategy::MergeDeduplicate)
+            .await
+        {
+            Ok(result) => log::info!(
+                "Imported {} legacy history entries from history.json",
+                result.entries_imported.unwrap_or(0)
+            ),
+            Err(e) => log::warn!("Failed to import legacy history.json: {}", e),
         }
+
+        let _ = fs::rename(&json_path, app_data_dir.join("history.json.bak"));
+    }
+
+    /// Reload the secret filter's user-supplied patterns without recompiling
+    pub fn reload_secret_filter_patterns(&self, patterns: Vec<String>) -> Result<(), String> {
+        let mut filter = self
+            .secret_filter
+            .write()
+            .map_err(|e| format!("Failed to write secret filter: {}", e))?;
+        filter.reload(&patterns)
+    }
+
+    /// Update the secret filter mode (off/redact/skip)
+    pub fn set_secret_filter_mode(&self, mode: SecretFilterMode) -> Result<(), String> {
+        let mut current = self
+            .secret_filter_mode
+            .write()
+            .map_err(|e| format!("Failed to write secret filter mode: {}", e))?;
+        *current = mode;
+        Ok(())
     }
 
-    /// Load history from the JSON file
-    fn load_from_file(file_path: &PathBuf) -> Option<HistoryData> {
-        let content = fs::read_to_string(file_path).ok()?;
-        serde_json::from_str(&content).ok()
+    /// Set the retention cap on stored entries, or `None` to keep everything
+    pub fn set_retention_limit(&self, limit: Option<usize>) -> Result<(), String> {
+        let mut current = self
+            .retention_limit
+            .write()
+            .map_err(|e| format!("Failed to write retention limit: {}", e))?;
+        *current = limit;
+        Ok(())
     }
 
-    /// Save current history to disk
-    fn save(&self) -> Result<(), String> {
-        let data = self
-            .data
+    /// Delete the oldest entries beyond the configured retention limit
+    async fn enforce_retention(&self) -> Result<(), String> {
+        let limit = *self
+            .retention_limit
             .read()
-            .map_err(|e| format!("Failed to read history: {}", e))?;
+            .map_err(|e| format!("Failed to read retention limit: {}", e))?;
 
-        let content = serde_json::to_string_pretty(&*data)
-            .map_err(|e| format!("Failed to serialize history: {}", e))?;
+        let Some(limit) = limit else {
+            return Ok(());
+        };
 
-        fs::write(&self.file_path, content)
-            .map_err(|e| format!("Failed to write history file: {}", e))?;
+        sqlx::query(
+            "DELETE FROM history WHERE id NOT IN (
+                SELECT id FROM history ORDER BY timestamp DESC, id DESC LIMIT ?1
+            )",
+        )
+        .bind(limit as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to enforce history retention: {}", e))?;
 
         Ok(())
     }
 
-    /// Add a new entry to the history
-    pub fn add_entry(&self, text: String, raw_text: String) -> Result<HistoryEntry, String> {
-        let entry = HistoryEntry::new(text, raw_text);
-        {
-            let mut data = self
-                .data
-                .write()
-                .map_err(|e| format!("Failed to write history: {}", e))?;
+    /// Read a small piece of persisted sync state (e.g. a history-sync
+    /// high-water mark) by key
+    pub async fn get_sync_meta(&self, key: &str) -> Result<Option<String>, String> {
+        let row: Option<(String,)> = sqlx::query_as("SELECT value FROM sync_meta WHERE key = ?1")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to read sync_meta: {}", e))?;
 
-            data.entries.insert(0, entry.clone());
+        Ok(row.map(|(value,)| value))
+    }
 
-            if data.entries.len() > MAX_HISTORY_ENTRIES {
-                data.entries.truncate(MAX_HISTORY_ENTRIES);
+    /// Persist a small piece of sync state (e.g. a history-sync high-water
+    /// mark) by key, overwriting any previous value
+    pub async fn set_sync_meta(&self, key: &str, value: &str) -> Result<(), String> {
+        sqlx::query(
+            "INSERT INTO sync_meta (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        )
+        .bind(key)
+        .bind(value)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to write sync_meta: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Newest entry (by timestamp, then id), if any, used for
+    /// consecutive-duplicate suppression
+    async fn newest_entry(&self) -> Result<Option<HistoryEntry>, String> {
+        let row: Option<HistoryRow> = sqlx::query_as(
+            "SELECT id, timestamp, text, raw_text FROM history ORDER BY timestamp DESC, id DESC LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to read newest history entry: {}", e))?;
+
+        Ok(row.map(HistoryEntry::from))
+    }
+
+    /// Add a new entry to the history. `opts` controls whitespace-only and
+    /// consecutive-duplicate suppression (mirroring rustyline's
+    /// `ignore_space` / `HistoryDuplicates::IgnoreConsecutive`); the text is
+    /// then run through the secret filter, which may redact or skip it
+    /// depending on `secret_filter_mode`.
+    pub async fn add_entry(
+        &self,
+        text: String,
+        raw_text: String,
+        opts: AddOptions,
+    ) -> Result<AddEntryOutcome, String> {
+        if opts.ignore_space && text.trim().is_empty() {
+            return Ok(AddEntryOutcome::Suppressed {
+                reason: SuppressReason::Whitespace,
+            });
+        }
+
+        if opts.ignore_dups {
+            if let Some(newest) = self.newest_entry().await? {
+                if newest.text.trim() == text.trim() {
+                    return Ok(AddEntryOutcome::Suppressed {
+                        reason: SuppressReason::ConsecutiveDuplicate,
+                    });
+                }
             }
         }
-        self.save()?;
-        Ok(entry)
-    }
 
-    /// Get all history entries (newest first), optionally limited
-    pub fn get_all(&self, limit: Option<usize>) -> Result<Vec<HistoryEntry>, String> {
-        let data = self
-            .data
+        let mode = *self
+            .secret_filter_mode
             .read()
-            .map_err(|e| format!("Failed to read history: {}", e))?;
-
-        let entries = match limit {
-            Some(n) => data.entries.iter().take(n).cloned().collect(),
-            None => data.entries.clone(),
+            .map_err(|e| format!("Failed to read secret filter mode: {}", e))?;
+
+        let (stored_text, redacted) = {
+            let filter = self
+                .secret_filter
+                .read()
+                .map_err(|e| format!("Failed to read secret filter: {}", e))?;
+            let matched = mode != SecretFilterMode::Off && filter.is_match(&text);
+
+            match (mode, matched) {
+                (_, false) | (SecretFilterMode::Off, _) => (text, false),
+                (SecretFilterMode::Skip, true) => {
+                    return Ok(AddEntryOutcome::Suppressed {
+                        reason: SuppressReason::Secret,
+                    });
+                }
+                (SecretFilterMode::Redact, true) => (filter.redact(&text), true),
+            }
         };
 
-        Ok(entries)
-    }
+        let entry = HistoryEntry::new(stored_text, raw_text);
 
-    /// Delete an entry by ID
-    pub fn delete(&self, id: &str) -> Result<bool, String> {
-        let deleted = {
-            let mut data = self
-                .data
-                .write()
-                .map_err(|e| format!("Failed to write history: {}", e))?;
-
-            let initial_len = data.entries.len();
-            data.entries.retain(|e| e.id != id);
-            data.entries.len() < initial_len
-        };
+        sqlx::query("INSERT INTO history (id, timestamp, text, raw_text) VALUES (?1, ?2, ?3, ?4)")
+            .bind(&entry.id)
+            .bind(entry.timestamp.timestamp_millis())
+            .bind(&entry.text)
+            .bind(&entry.raw_text)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to insert history entry: {}", e))?;
+
+        self.enforce_retention().await?;
+
+        Ok(AddEntryOutcome::Stored { entry, redacted })
+    }
 
-        if deleted {
-            self.save()?;
+    /// Get all history entries (newest first), optionally limited
+    pub async fn get_all(&self, limit: Option<usize>) -> Result<Vec<HistoryEntry>, String> {
+        let rows: Vec<HistoryRow> = match limit {
+            Some(n) => {
+                sqlx::query_as("SELECT id, timestamp, text, raw_text FROM history ORDER BY timestamp DESC, id DESC LIMIT ?1")
+                    .bind(n as i64)
+                    .fetch_all(&self.pool)
+                    .await
+            }
+            None => {
+                sqlx::query_as(
+                    "SELECT id, timestamp, text, raw_text FROM history ORDER BY timestamp DESC, id DESC",
+                )
+                .fetch_all(&self.pool)
+                .await
+            }
         }
+        .map_err(|e| format!("Failed to read history: {}", e))?;
+
+        Ok(rows.into_iter().map(HistoryEntry::from).collect())
+    }
 
-        Ok(deleted)
+    /// Search history entries for `query` using the given mode and paging
+    /// options (see `search::search`)
+    pub async fn search(&self, query: &str, opts: SearchOpts) -> Result<Vec<SearchMatch>, String> {
+        let entries = self.get_all(None).await?;
+        Ok(search::search(&entries, query, &opts))
+    }
+
+    /// Delete an entry by ID
+    pub async fn delete(&self, id: &str) -> Result<bool, String> {
+        let result = sqlx::query("DELETE FROM history WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to delete history entry: {}", e))?;
+
+        Ok(result.rows_affected() > 0)
     }
 
     /// Clear all history
-    pub fn clear(&self) -> Result<(), String> {
-        {
-            let mut data = self
-                .data
-                .write()
-                .map_err(|e| format!("Failed to write history: {}", e))?;
-            data.entries.clear();
-        }
-        self.save()
+    pub async fn clear(&self) -> Result<(), String> {
+        sqlx::query("DELETE FROM history")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to clear history: {}", e))?;
+
+        Ok(())
     }
 
     /// Import entries with the specified strategy
-    pub fn import_entries(
+    pub async fn import_entries(
         &self,
-        mut entries: Vec<HistoryEntry>,
+        entries: Vec<HistoryEntry>,
         strategy: HistoryImportStrategy,
     ) -> Result<HistoryImportResult, String> {
-        let imported_count;
-        let skipped_count;
-
-        {
-            let mut data = self
-                .data
-                .write()
-                .map_err(|e| format!("Failed to write history: {}", e))?;
-
-            match strategy {
-                HistoryImportStrategy::Replace => {
-                    // Sort imported entries by timestamp (newest first)
-                    entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-                    imported_count = entries.len();
-                    skipped_count = 0;
-                    data.entries = entries;
-                }
-                HistoryImportStrategy::MergeAppend => {
-                    // Prepend imported entries (imported are considered newer)
-                    // Sort imported entries by timestamp (newest first)
-                    entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-                    imported_count = entries.len();
-                    skipped_count = 0;
-
-                    // Prepend imported entries to existing
-                    let mut combined = entries;
-                    combined.append(&mut data.entries);
-                    data.entries = combined;
-                }
-                HistoryImportStrategy::MergeDeduplicate => {
-                    // Collect existing IDs
-                    let existing_ids: HashSet<String> =
-                        data.entries.iter().map(|e| e.id.clone()).collect();
-
-                    // Filter out entries that already exist
-                    let new_entries: Vec<HistoryEntry> = entries
-                        .into_iter()
-                        .filter(|e| !existing_ids.contains(&e.id))
-                        .collect();
-
-                    imported_count = new_entries.len();
-                    skipped_count = 0; // We'll calculate this from the original count
-
-                    // Prepend new entries
-                    let mut combined = new_entries;
-                    combined.append(&mut data.entries);
-
-                    // Sort by timestamp (newest first)
-                    combined.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-                    data.entries = combined;
-                }
-            }
+        if matches!(strategy, HistoryImportStrategy::Replace) {
+            self.clear().await?;
+        }
 
-            // Truncate to max entries
-            if data.entries.len() > MAX_HISTORY_ENTRIES {
-                data.entries.truncate(MAX_HISTORY_ENTRIES);
+        let original_len = entries.len();
+
+        // MergeAppend and MergeDeduplicate both skip entries whose ID
+        // already exists; INSERT OR IGNORE on the primary key gives us
+        // that for free, and ordering at read time by `timestamp, id` keeps
+        // imported entries correctly interleaved (and ties broken by the
+        // sortable UUIDv7 id) regardless of strategy.
+        let mut imported_count = 0;
+        for entry in &entries {
+            let result = sqlx::query(
+                "INSERT OR IGNORE INTO history (id, timestamp, text, raw_text) VALUES (?1, ?2, ?3, ?4)",
+            )
+            .bind(&entry.id)
+            .bind(entry.timestamp.timestamp_millis())
+            .bind(&entry.text)
+            .bind(&entry.raw_text)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to import history entry: {}", e))?;
+
+            if result.rows_affected() > 0 {
+                imported_count += 1;
             }
         }
 
-        self.save()?;
+        self.enforce_retention().await?;
+
+        let entries_skipped = original_len - imported_count;
 
         Ok(HistoryImportResult {
             success: true,
             entries_imported: Some(imported_count),
-            entries_skipped: Some(skipped_count),
+            entries_skipped: Some(entries_skipped),
         })
     }
 }
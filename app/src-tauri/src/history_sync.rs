@@ -0,0 +1,257 @@
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chacha20poly1305::{aead::Aead, KeyInit, XChaCha20Poly1305, XNonce};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri_plugin_http::reqwest::Client;
+use tokio::sync::RwLock;
+
+use crate::history::{HistoryEntry, HistoryImportStrategy, HistoryStorage};
+
+/// Event emitted after an upload/download attempt so the frontend can
+/// surface sync state, mirroring `ConfigResponse`'s success/error shape
+pub const HISTORY_SYNC_EVENT: &str = "history-sync-response";
+
+/// Outcome of a history sync attempt, emitted as `HISTORY_SYNC_EVENT`
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum HistorySyncResponse {
+    Uploaded { count: usize },
+    Downloaded { count: usize },
+    Error { message: String },
+}
+
+/// An entry encrypted client-side before upload: the id stays in the clear
+/// so the server can store/list records, but `text`/`raw_text`/`timestamp`
+/// never leave the device unencrypted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedHistoryRecord {
+    pub id: String,
+    /// Base64-encoded 24-byte XChaCha20-Poly1305 nonce
+    pub nonce: String,
+    /// Base64-encoded ciphertext of the serialized `HistoryEntry`
+    pub ciphertext: String,
+}
+
+/// Key `last_synced_id` is persisted under in `HistoryStorage`'s sync_meta
+/// table, so the high-water mark survives reconnects and app restarts
+const LAST_SYNCED_ID_KEY: &str = "history_sync_last_synced_id";
+
+/// Tracks server connection state and the encryption key for history sync
+pub struct HistorySyncState {
+    client: Client,
+    server_url: Option<String>,
+    client_uuid: Option<String>,
+    key: Option<[u8; 32]>,
+    /// Highest UUIDv7 entry id already uploaded; IDs sort the same as
+    /// creation time, so this is a simple high-water mark. Persisted to
+    /// `HistoryStorage` so it isn't lost (and the entire history re-synced)
+    /// on every reconnect or restart.
+    last_synced_id: Option<String>,
+}
+
+impl Default for HistorySyncState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HistorySyncState {
+    pub fn new() -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()
+                .expect("Failed to create HTTP client"),
+            server_url: None,
+            client_uuid: None,
+            key: None,
+            last_synced_id: None,
+        }
+    }
+
+    /// Derive the encryption key from the user's passphrase, connect, and
+    /// restore the last-synced high-water mark from the previous session
+    pub async fn set_connected(
+        &mut self,
+        server_url: String,
+        client_uuid: String,
+        passphrase: &str,
+        history: &HistoryStorage,
+    ) -> Result<(), String> {
+        let key = derive_key(passphrase, client_uuid.as_bytes())?;
+        self.server_url = Some(server_url);
+        self.client_uuid = Some(client_uuid);
+        self.key = Some(key);
+        self.last_synced_id = history.get_sync_meta(LAST_SYNCED_ID_KEY).await?;
+        Ok(())
+    }
+
+    /// Clear connection info and the in-memory key when disconnected. The
+    /// high-water mark itself is left in `HistoryStorage` so the next
+    /// connect resumes incremental uploads instead of starting over.
+    pub fn set_disconnected(&mut self) {
+        self.server_url = None;
+        self.client_uuid = None;
+        self.key = None;
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.server_url.is_some() && self.client_uuid.is_some() && self.key.is_some()
+    }
+
+    /// Encrypt and upload every local entry whose id sorts after the
+    /// last-synced id. Entries are selected by comparing ids, not by their
+    /// position in the newest-first list, so an entry whose id sorts at or
+    /// before the watermark (e.g. an older entry imported from another
+    /// device via `download_entries`) is not uploaded.
+    pub async fn upload_new_entries(&mut self, history: &HistoryStorage) -> Result<usize, String> {
+        let (url, uuid, key) = match (&self.server_url, &self.client_uuid, &self.key) {
+            (Some(u), Some(id), Some(k)) => (u.clone(), id.clone(), *k),
+            _ => return Ok(0),
+        };
+
+        let entries = history.get_all(None).await?;
+        let pending: Vec<&HistoryEntry> = match &self.last_synced_id {
+            Some(last) => entries
+                .iter()
+                .filter(|e| e.id.as_str() > last.as_str())
+                .collect(),
+            None => entries.iter().collect(),
+        };
+
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let records = pending
+            .iter()
+            .map(|e| encrypt_entry(&key, e))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.client
+            .put(format!("{}/api/history/sync", url))
+            .header("X-Client-UUID", &uuid)
+            .json(&records)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .error_for_status()
+            .map_err(|e| e.to_string())?;
+
+        // Entries are newest-first, so the first pending entry has the
+        // greatest (most recent) id
+        if let Some(newest) = pending.first() {
+            self.last_synced_id = Some(newest.id.clone());
+            history
+                .set_sync_meta(LAST_SYNCED_ID_KEY, &newest.id)
+                .await?;
+        }
+
+        log::debug!("Uploaded {} history entries to server", records.len());
+        Ok(records.len())
+    }
+
+    /// Fetch, decrypt, and merge remote entries into local history. Does
+    /// *not* touch the upload high-water mark: that mark tracks what this
+    /// device has uploaded, which is independent of what it has downloaded,
+    /// and advancing it here previously caused pre-existing local entries
+    /// older than the newest remote entry to be silently skipped by the
+    /// next upload pass. A subsequent upload may re-send entries that were
+    /// just downloaded; the server is expected to dedupe by id.
+    pub async fn download_entries(&self, history: &HistoryStorage) -> Result<usize, String> {
+        let (url, uuid, key) = match (&self.server_url, &self.client_uuid, &self.key) {
+            (Some(u), Some(id), Some(k)) => (u.clone(), id.clone(), *k),
+            _ => return Ok(0),
+        };
+
+        let records: Vec<EncryptedHistoryRecord> = self
+            .client
+            .get(format!("{}/api/history/sync", url))
+            .header("X-Client-UUID", &uuid)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .error_for_status()
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let entries = records
+            .iter()
+            .map(|r| decrypt_entry(&key, r))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let result = history
+            .import_entries(entries, HistoryImportStrategy::MergeDeduplicate)
+            .await?;
+
+        let imported = result.entries_imported.unwrap_or(0);
+        log::debug!("Downloaded {} new history entries from server", imported);
+        Ok(imported)
+    }
+}
+
+pub type HistorySync = Arc<RwLock<HistorySyncState>>;
+
+pub fn new_history_sync() -> HistorySync {
+    Arc::new(RwLock::new(HistorySyncState::new()))
+}
+
+/// Derive a 32-byte key from the user's passphrase via Argon2. `salt` only
+/// needs to be stable per account, not secret, so the client UUID doubles
+/// as salt material.
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive encryption key: {}", e))?;
+    Ok(key)
+}
+
+fn encrypt_entry(key: &[u8; 32], entry: &HistoryEntry) -> Result<EncryptedHistoryRecord, String> {
+    let cipher = XChaCha20Poly1305::new(key.into());
+
+    let mut nonce_bytes = [0u8; 24];
+    OsRng.fill_bytes(&mut nonce_bytes);
+    let nonce = XNonce::from_slice(&nonce_bytes);
+
+    let plaintext =
+        serde_json::to_vec(entry).map_err(|e| format!("Failed to serialize entry: {}", e))?;
+    let ciphertext = cipher
+        .encrypt(nonce, plaintext.as_ref())
+        .map_err(|e| format!("Failed to encrypt entry: {}", e))?;
+
+    Ok(EncryptedHistoryRecord {
+        id: entry.id.clone(),
+        nonce: STANDARD.encode(nonce_bytes),
+        ciphertext: STANDARD.encode(ciphertext),
+    })
+}
+
+fn decrypt_entry(key: &[u8; 32], record: &EncryptedHistoryRecord) -> Result<HistoryEntry, String> {
+    let cipher = XChaCha20Poly1305::new(key.into());
+
+    let nonce_bytes = STANDARD
+        .decode(&record.nonce)
+        .map_err(|e| format!("Invalid nonce: {}", e))?;
+    if nonce_bytes.len() != 24 {
+        return Err(format!(
+            "Invalid nonce: expected 24 bytes, got {}",
+            nonce_bytes.len()
+        ));
+    }
+    let nonce = XNonce::from_slice(&nonce_bytes);
+
+    let ciphertext = STANDARD
+        .decode(&record.ciphertext)
+        .map_err(|e| format!("Invalid ciphertext: {}", e))?;
+    let plaintext = cipher
+        .decrypt(nonce, ciphertext.as_ref())
+        .map_err(|e| format!("Failed to decrypt entry: {}", e))?;
+
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Failed to deserialize entry: {}", e))
+}
@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+
+use crate::history::HistoryEntry;
+
+/// How `search` matches entry text against the query
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    /// Query must appear anywhere in the text (case-insensitive)
+    Substring,
+    /// Text must start with the query (case-insensitive)
+    Prefix,
+    /// Query characters must appear in order, not necessarily contiguous
+    Fuzzy,
+}
+
+/// Paging direction when resuming from a cursor position, mirroring
+/// rustyline's `Direction`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchDirection {
+    /// Page from the cursor towards the newest entry
+    Forward,
+    /// Page from the cursor towards the oldest entry
+    Reverse,
+}
+
+impl Default for SearchDirection {
+    fn default() -> Self {
+        SearchDirection::Reverse
+    }
+}
+
+/// Options controlling a history search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchOpts {
+    pub mode: SearchMode,
+    #[serde(default)]
+    pub direction: SearchDirection,
+    /// Index into the newest-first entry list to start paging from
+    #[serde(default)]
+    pub cursor: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+/// A single search hit with the ranges that matched, so the UI can
+/// highlight them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchMatch {
+    pub entry: HistoryEntry,
+    pub score: i64,
+    pub ranges: Vec<(usize, usize)>,
+}
+
+/// Run `query` against `entries` (assumed newest-first) using `opts`
+pub fn search(entries: &[HistoryEntry], query: &str, opts: &SearchOpts) -> Vec<SearchMatch> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let indices = paged_indices(entries.len(), opts);
+
+    let mut matches: Vec<SearchMatch> = indices
+        .into_iter()
+        .filter_map(|i| {
+            let entry = &entries[i];
+            let hit = match opts.mode {
+                SearchMode::Substring => {
+                    match_substring(&entry.text, query).map(|ranges| (0, ranges))
+                }
+                SearchMode::Prefix => match_prefix(&entry.text, query).map(|ranges| (0, ranges)),
+                SearchMode::Fuzzy => fuzzy_score(&entry.text, query),
+            };
+            hit.map(|(score, ranges)| SearchMatch {
+                entry: entry.clone(),
+                score,
+                ranges,
+            })
+        })
+        .collect();
+
+    match opts.mode {
+        SearchMode::Fuzzy => {
+            matches.sort_by(|a, b| {
+                b.score
+                    .cmp(&a.score)
+                    .then_with(|| b.entry.timestamp.cmp(&a.entry.timestamp))
+            });
+            if let Some(limit) = opts.limit {
+                matches.truncate(limit);
+            }
+        }
+        SearchMode::Substring | SearchMode::Prefix => {
+            // Cap in scan order so the page holds the hits nearest the cursor
+            if let Some(limit) = opts.limit {
+                matches.truncate(limit);
+            }
+            matches.sort_by(|a, b| b.entry.timestamp.cmp(&a.entry.timestamp))
+        }
+    }
+
+    matches
+}
+
+/// Returns the entry indices to scan, in scan order, given the cursor and
+/// direction (entries are newest-first, so index 0 is the newest)
+fn paged_indices(len: usize, opts: &SearchOpts) -> Vec<usize> {
+    let start = opts.cursor.unwrap_or(0).min(len);
+    match opts.direction {
+        SearchDirection::Forward => (0..start).rev().collect(),
+        SearchDirection::Reverse => (start..len).collect(),
+    }
+}
+
+/// Lowercase `text` one char at a time (keeping only the first char of each
+/// char's lowercasing), so the result has exactly as many chars as `text`.
+/// `str::to_lowercase()` can change the char count for some code points
+/// (e.g. `'İ'` expands to 2 chars), which would desync any index computed
+/// against it from an index into the original text; this keeps all of
+/// `match_substring`/`match_prefix`/`fuzzy_score`'s char indices
+/// interchangeable with indices into the original `text`.
+fn lower_chars(chars: &[char]) -> Vec<char> {
+    chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect()
+}
+
+fn match_substring(text: &str, query: &str) -> Option<Vec<(usize, usize)>> {
+    let chars: Vec<char> = text.chars().collect();
+    let lower_text = lower_chars(&chars);
+    let lower_query = lower_chars(&query.chars().collect::<Vec<char>>());
+
+    if lower_query.is_empty() || lower_query.len() > lower_text.len() {
+        return None;
+    }
+
+    let start = lower_text
+        .windows(lower_query.len())
+        .position(|window| window == lower_query.as_slice())?;
+    Some(vec![(start, start + lower_query.len())])
+}
+
+fn match_prefix(text: &str, query: &str) -> Option<Vec<(usize, usize)>> {
+    let chars: Vec<char> = text.chars().collect();
+    let lower_text = lower_chars(&chars);
+    let lower_query = lower_chars(&query.chars().collect::<Vec<char>>());
+
+    if lower_query.len() > lower_text.len() {
+        return None;
+    }
+
+    (lower_text[..lower_query.len()] == lower_query[..]).then(|| vec![(0, lower_query.len())])
+}
+
+/// Subsequence-with-gap-penalty fuzzy scorer: all query chars must appear in
+/// order (case-insensitive). Contiguous runs and word-boundary starts score
+/// higher; gaps between matched characters are penalized. Returned ranges
+/// are char indices into the original `text`, matching `match_substring`/
+/// `match_prefix`.
+fn fuzzy_score(text: &str, query: &str) -> Option<(i64, Vec<(usize, usize)>)> {
+    let chars: Vec<char> = text.chars().collect();
+    let lower_text = lower_chars(&chars);
+    let query_chars = lower_chars(&query.chars().collect::<Vec<char>>());
+
+    if query_chars.is_empty() {
+        return None;
+    }
+
+    let mut score: i64 = 0;
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    let mut text_idx = 0;
+    let mut prev_matched_idx: Option<usize> = None;
+
+    for &qc in &query_chars {
+        let matched_idx = loop {
+            if text_idx >= lower_text.len() {
+                return None;
+            }
+            if lower_text[text_idx] == qc {
+                break text_idx;
+            }
+            text_idx += 1;
+        };
+
+        let is_word_boundary = matched_idx == 0 || !chars[matched_idx - 1].is_alphanumeric();
+        let is_contiguous = prev_matched_idx == Some(matched_idx.wrapping_sub(1));
+
+        score += 10;
+        if is_contiguous {
+            score += 15;
+        }
+        if is_word_boundary {
+            score += 10;
+        }
+        if let Some(prev) = prev_matched_idx {
+            let gap = matched_idx - prev - 1;
+            score -= gap as i64;
+        }
+
+        match ranges.last_mut() {
+            Some((_, last_end)) if is_contiguous => *last_end = matched_idx + 1,
+            _ => ranges.push((matched_idx, matched_idx + 1)),
+        }
+
+        prev_matched_idx = Some(matched_idx);
+        text_idx += 1;
+    }
+
+    Some((score, ranges))
+}
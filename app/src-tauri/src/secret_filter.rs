@@ -0,0 +1,94 @@
+use regex::{Regex, RegexSet};
+use serde::{Deserialize, Serialize};
+
+/// How `HistoryStorage` should react when a secret-like pattern matches.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretFilterMode {
+    /// Don't scan entries for secrets at all
+    Off,
+    /// Store a redacted copy with each match span replaced
+    Redact,
+    /// Drop the entry entirely instead of storing it
+    Skip,
+}
+
+impl Default for SecretFilterMode {
+    fn default() -> Self {
+        Self::Redact
+    }
+}
+
+/// Text substituted for each match span when redacting
+const REDACTION_PLACEHOLDER: &str = "***REDACTED***";
+
+/// Default secret-like patterns scanned before a history entry is stored.
+/// Mirrors Atuin's built-in secrets filter: cloud provider keys, PEM blocks,
+/// long hex/base64 blobs, and card-like digit runs.
+const DEFAULT_PATTERNS: &[&str] = &[
+    r"AKIA[0-9A-Z]{16}",
+    r"-----BEGIN [A-Z ]*PRIVATE KEY-----",
+    r"\b[A-Fa-f0-9]{32,}\b",
+    r"\b[A-Za-z0-9+/]{40,}={0,2}\b",
+    r"\b(?:\d[ -]?){13,16}\b",
+];
+
+/// Scans dictated text against a compiled set of secret/PII patterns, and
+/// can redact the matched spans in place.
+pub struct SecretFilter {
+    set: RegexSet,
+    patterns: Vec<Regex>,
+}
+
+impl SecretFilter {
+    /// Build a filter from the default pattern list plus any user-supplied
+    /// patterns from `AppSettings`.
+    pub fn new(extra_patterns: &[String]) -> Result<Self, String> {
+        let all: Vec<String> = DEFAULT_PATTERNS
+            .iter()
+            .map(|p| p.to_string())
+            .chain(extra_patterns.iter().cloned())
+            .collect();
+        Self::from_patterns(all)
+    }
+
+    /// Build a filter from only the built-in default patterns. Used as a
+    /// fallback when user-supplied patterns fail to compile.
+    pub fn default_only() -> Self {
+        Self::from_patterns(DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect())
+            .expect("default secret patterns must compile")
+    }
+
+    fn from_patterns(all: Vec<String>) -> Result<Self, String> {
+        let set = RegexSet::new(&all).map_err(|e| format!("Invalid secret pattern: {}", e))?;
+        let patterns = all
+            .iter()
+            .map(|p| Regex::new(p).map_err(|e| format!("Invalid secret pattern '{}': {}", p, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { set, patterns })
+    }
+
+    /// Reload the pattern set, e.g. after the user edits custom patterns
+    /// without restarting the app.
+    pub fn reload(&mut self, extra_patterns: &[String]) -> Result<(), String> {
+        *self = Self::new(extra_patterns)?;
+        Ok(())
+    }
+
+    /// Returns true if any pattern matches the given text.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.set.is_match(text)
+    }
+
+    /// Redact all matches in `text`, returning the redacted copy.
+    pub fn redact(&self, text: &str) -> String {
+        let mut redacted = text.to_string();
+        for pattern in &self.patterns {
+            redacted = pattern
+                .replace_all(&redacted, REDACTION_PLACEHOLDER)
+                .into_owned();
+        }
+        redacted
+    }
+}
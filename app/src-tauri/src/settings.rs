@@ -4,6 +4,8 @@ use std::str::FromStr;
 #[cfg(desktop)]
 use tauri_plugin_global_shortcut::Shortcut;
 
+use crate::secret_filter::SecretFilterMode;
+
 // ============================================================================
 // DEFAULT SETTINGS CONSTANTS - Single source of truth for all defaults
 // ============================================================================
@@ -56,6 +58,18 @@ pub enum StoreKey {
     SttTimeoutSeconds,
     /// Server URL
     ServerUrl,
+    /// Secret/PII redaction mode for history entries
+    SecretFilterMode,
+    /// User-supplied secret redaction patterns
+    SecretFilterPatterns,
+    /// Whether to skip storing consecutive-duplicate history entries
+    HistoryIgnoreDups,
+    /// Whether to skip storing whitespace-only history entries
+    HistoryIgnoreSpace,
+    /// Custom sound pack configuration for recording start/stop cues
+    SoundConfig,
+    /// Selected audio output device ID for recording cue playback
+    SelectedOutputDeviceId,
 }
 
 impl StoreKey {
@@ -73,6 +87,12 @@ impl StoreKey {
             Self::AutoMuteAudio => "auto_mute_audio",
             Self::SttTimeoutSeconds => "stt_timeout_seconds",
             Self::ServerUrl => "server_url",
+            Self::SecretFilterMode => "secret_filter_mode",
+            Self::SecretFilterPatterns => "secret_filter_patterns",
+            Self::HistoryIgnoreDups => "history_ignore_dups",
+            Self::HistoryIgnoreSpace => "history_ignore_space",
+            Self::SoundConfig => "sound_config",
+            Self::SelectedOutputDeviceId => "selected_output_device_id",
         }
     }
 }
@@ -198,6 +218,71 @@ pub struct CleanupPromptSections {
     pub dictionary: PromptSection,
 }
 
+// ============================================================================
+// SOUND CONFIG TYPES
+// ============================================================================
+
+/// Default per-cue gain, applied on top of the engine's master volume
+fn default_cue_volume() -> f32 {
+    1.0
+}
+
+/// Configuration for a single recording cue (start or stop): an optional
+/// custom sound file, its own volume, and an optional `start_ms..end_ms`
+/// trim so a longer clip can be clipped to a short blip
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SoundCueConfig {
+    /// Path to a custom WAV/FLAC/Vorbis/MP3 file; falls back to the
+    /// embedded default if unset, missing, or undecodable
+    pub path: Option<String>,
+    #[serde(default = "default_cue_volume")]
+    pub volume: f32,
+    pub start_ms: Option<u64>,
+    pub end_ms: Option<u64>,
+}
+
+impl Default for SoundCueConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            volume: default_cue_volume(),
+            start_ms: None,
+            end_ms: None,
+        }
+    }
+}
+
+/// Sound pack configuration for both recording cues
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct SoundConfig {
+    pub recording_start: SoundCueConfig,
+    pub recording_stop: SoundCueConfig,
+}
+
+/// Validate a cue's path and trim range, as required before it's saved to
+/// `AppSettings`
+pub fn validate_sound_cue_config(cue: &SoundCueConfig) -> Result<(), SettingsError> {
+    if let Some(path) = &cue.path {
+        if path.trim().is_empty() {
+            return Err(SettingsError::InvalidValue {
+                field: "sound_config".to_string(),
+                message: "path must not be empty".to_string(),
+            });
+        }
+    }
+
+    if let (Some(start_ms), Some(end_ms)) = (cue.start_ms, cue.end_ms) {
+        if end_ms <= start_ms {
+            return Err(SettingsError::InvalidValue {
+                field: "sound_config".to_string(),
+                message: "end_ms must be greater than start_ms".to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
 // ============================================================================
 // APP SETTINGS - Complete settings structure
 // ============================================================================
@@ -216,6 +301,12 @@ pub struct AppSettings {
     pub auto_mute_audio: bool,
     pub stt_timeout_seconds: Option<f64>,
     pub server_url: String,
+    pub secret_filter_mode: SecretFilterMode,
+    pub secret_filter_patterns: Vec<String>,
+    pub history_ignore_dups: bool,
+    pub history_ignore_space: bool,
+    pub sound_config: SoundConfig,
+    pub selected_output_device_id: Option<String>,
 }
 
 impl Default for AppSettings {
@@ -232,6 +323,12 @@ impl Default for AppSettings {
             auto_mute_audio: false,
             stt_timeout_seconds: None,
             server_url: DEFAULT_SERVER_URL.to_string(),
+            secret_filter_mode: SecretFilterMode::default(),
+            secret_filter_patterns: Vec::new(),
+            history_ignore_dups: true,
+            history_ignore_space: true,
+            sound_config: SoundConfig::default(),
+            selected_output_device_id: None,
         }
     }
 }
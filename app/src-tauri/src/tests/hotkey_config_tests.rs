@@ -1,3 +1,4 @@
+use crate::secret_filter::SecretFilterMode;
 use crate::settings::{check_hotkey_conflict, AppSettings, HotkeyConfig, HotkeyType, StoreKey};
 
 // Tests for HotkeyConfig::to_shortcut_string()
@@ -194,6 +195,13 @@ fn test_app_settings_default() {
     assert!(settings.cleanup_prompt_sections.is_none());
     assert!(settings.stt_timeout_seconds.is_none());
     assert_eq!(settings.server_url, "http://127.0.0.1:8765");
+    assert_eq!(settings.secret_filter_mode, SecretFilterMode::Redact);
+    assert!(settings.secret_filter_patterns.is_empty());
+    assert!(settings.history_ignore_dups);
+    assert!(settings.history_ignore_space);
+    assert!(settings.sound_config.recording_start.path.is_none());
+    assert_eq!(settings.sound_config.recording_start.volume, 1.0);
+    assert!(settings.selected_output_device_id.is_none());
 }
 
 // Tests for HotkeyType
@@ -0,0 +1,48 @@
+use crate::settings::{validate_sound_cue_config, SettingsError, SoundCueConfig};
+
+#[test]
+fn test_validate_sound_cue_config_defaults_ok() {
+    let cue = SoundCueConfig::default();
+    assert!(validate_sound_cue_config(&cue).is_ok());
+}
+
+#[test]
+fn test_validate_sound_cue_config_valid_trim_range() {
+    let cue = SoundCueConfig {
+        path: Some("/tmp/cue.wav".to_string()),
+        start_ms: Some(100),
+        end_ms: Some(500),
+        ..SoundCueConfig::default()
+    };
+    assert!(validate_sound_cue_config(&cue).is_ok());
+}
+
+#[test]
+fn test_validate_sound_cue_config_rejects_end_before_start() {
+    let cue = SoundCueConfig {
+        start_ms: Some(500),
+        end_ms: Some(100),
+        ..SoundCueConfig::default()
+    };
+    let err = validate_sound_cue_config(&cue).unwrap_err();
+    assert!(matches!(err, SettingsError::InvalidValue { .. }));
+}
+
+#[test]
+fn test_validate_sound_cue_config_rejects_equal_start_end() {
+    let cue = SoundCueConfig {
+        start_ms: Some(200),
+        end_ms: Some(200),
+        ..SoundCueConfig::default()
+    };
+    assert!(validate_sound_cue_config(&cue).is_err());
+}
+
+#[test]
+fn test_validate_sound_cue_config_rejects_empty_path() {
+    let cue = SoundCueConfig {
+        path: Some("   ".to_string()),
+        ..SoundCueConfig::default()
+    };
+    assert!(validate_sound_cue_config(&cue).is_err());
+}